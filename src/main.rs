@@ -1,18 +1,56 @@
-use num::Zero;
 use std::clone::Clone;
+use std::cmp::Ordering;
 use std::ops::Add;
+use std::ops::Range;
+
+/// The reference-counted pointer nodes are stored behind. Plain `Rc` by
+/// default; build with `--features sync` to get thread-safe `Arc` nodes
+/// instead (at the usual cost of atomic instead of plain refcounting).
+#[cfg(not(feature = "sync"))]
+type Shared<T> = std::rc::Rc<T>;
+#[cfg(feature = "sync")]
+type Shared<T> = std::sync::Arc<T>;
 
 trait Measured<M> {
     fn measure(&self) -> M;
 }
 
-#[derive(Debug)]
+/// A measure that can be combined with itself and has a neutral element,
+/// i.e. `combine(identity(), m) == combine(m, identity()) == m`.
+///
+/// This is deliberately weaker than `num::Zero` + `std::ops::Add`: it doesn't
+/// require measures to behave like numbers, so it also fits monoids like
+/// string concatenation or a "maximum so far" summary.
+trait Monoid {
+    fn identity() -> Self;
+    fn combine(&self, other: &Self) -> Self;
+}
+
+/// A projection of a measure onto something a `Cursor` can seek on, e.g. a
+/// plain byte offset projected out of a richer summary.
+trait Dimension<M> {
+    fn zero() -> Self;
+    fn add_summary(&mut self, summary: &M);
+}
+
+/// Something a `Cursor` can seek towards: `cmp` compares the target against
+/// the dimension accumulated so far, the same way the old boolean `split`
+/// predicate compared an offset against a running measure.
+trait SeekTarget<M, D: Dimension<M>> {
+    fn cmp(&self, dimension: &D) -> Ordering;
+}
+
+/// `Fork` holds its node behind `Shared` rather than owning it outright, so
+/// that cloning a `SplayTree` (and thus a `Rope::snapshot`) is an O(1)
+/// refcount bump instead of a deep copy, and untouched subtrees can be
+/// shared between the tree before and after an edit.
+#[derive(Clone, Debug)]
 enum SplayTree<M, T> {
     Leaf,
-    Fork(Box<SplayTreeFork<M, T>>),
+    Fork(Shared<SplayTreeFork<M, T>>),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct SplayTreeFork<M, T> {
     left: SplayTree<M, T>,
     element: T,
@@ -22,10 +60,23 @@ struct SplayTreeFork<M, T> {
 
 use SplayTree::*;
 
-impl<M: Clone + Zero, T> Measured<M> for SplayTree<M, T> {
+/// Takes ownership of a shared node for path-copying: if we hold the only
+/// reference, this is free; otherwise it clones the node (not its
+/// subtrees, which stay shared `Shared` pointers) so the rest of the tree
+/// is unaffected. This is the consuming counterpart of `Rc::make_mut`/
+/// `Arc::make_mut`, which need a place to write back into instead of an
+/// owned value to move out of.
+fn make_owned<M: Clone, T: Clone>(fork: Shared<SplayTreeFork<M, T>>) -> SplayTreeFork<M, T> {
+    match Shared::try_unwrap(fork) {
+        Result::Ok(owned) => owned,
+        Result::Err(shared) => (*shared).clone(),
+    }
+}
+
+impl<M: Clone + Monoid, T> Measured<M> for SplayTree<M, T> {
     fn measure(&self) -> M {
         match self {
-            Leaf => M::zero(),
+            Leaf => M::identity(),
             Fork(fork) => (*fork).measure.clone(),
         }
     }
@@ -74,9 +125,9 @@ impl<'a, M, T> From<&'a SplayTree<M, T>> for SplayTreeIterator<'a, M, T> {
     }
 }
 
-impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
+impl<M: Clone + Monoid, T: Measured<M> + Clone> SplayTree<M, T> {
     fn fork_measure(l: SplayTree<M, T>, t: T, r: SplayTree<M, T>, m: M) -> SplayTree<M, T> {
-        Fork(Box::new(SplayTreeFork {
+        Fork(Shared::new(SplayTreeFork {
             left: l,
             element: t,
             right: r,
@@ -84,7 +135,7 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
         }))
     }
     fn fork(l: SplayTree<M, T>, t: T, r: SplayTree<M, T>) -> SplayTree<M, T> {
-        let m = l.measure() + t.measure() + r.measure();
+        let m = l.measure().combine(&t.measure()).combine(&r.measure());
         SplayTree::fork_measure(l, t, r, m)
     }
 
@@ -98,12 +149,13 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
     fn uncons(self) -> Option<(T, SplayTree<M, T>)> {
         match self {
             Leaf => Option::None,
-            Fork(fork_box) => {
-                let mut fork = *fork_box;
+            Fork(fork_rc) => {
+                let mut fork = make_owned(fork_rc);
                 loop {
                     match fork.left {
                         Leaf => break Option::Some((fork.element, fork.right)),
-                        Fork(left_fork) => {
+                        Fork(left_fork_rc) => {
+                            let left_fork = make_owned(left_fork_rc);
                             fork = SplayTreeFork {
                                 left: left_fork.left,
                                 element: left_fork.element,
@@ -120,12 +172,13 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
     fn unsnoc(self) -> Option<(SplayTree<M, T>, T)> {
         match self {
             Leaf => Option::None,
-            Fork(fork_box) => {
-                let mut fork = *fork_box;
+            Fork(fork_rc) => {
+                let mut fork = make_owned(fork_rc);
                 loop {
                     match fork.right {
                         Leaf => break Option::Some((fork.left, fork.element)),
-                        Fork(right_fork) => {
+                        Fork(right_fork_rc) => {
+                            let right_fork = make_owned(right_fork_rc);
                             fork = SplayTreeFork {
                                 left: SplayTree::fork(fork.left, fork.element, right_fork.left),
                                 element: right_fork.element,
@@ -140,7 +193,7 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
     }
 
     fn split<F: Fn(&M) -> bool>(self, pred: F) -> SplitResult<M, T> {
-        let mut v = M::zero();
+        let mut v = M::identity();
         let mut left = Leaf;
         let mut tree = self;
         let mut right = Leaf;
@@ -163,16 +216,17 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
                     };
                 }
 
-                Fork(fork) => {
-                    let vl = v.clone() + fork.left.measure();
+                Fork(fork_rc) => {
+                    let fork = make_owned(fork_rc);
+                    let vl = v.combine(&fork.left.measure());
                     if pred(&vl) {
                         tree = fork.left;
                         right = SplayTree::from(fork.element) + fork.right + right;
                         continue;
                     }
-                    let vla = vl.clone() + fork.element.measure();
+                    let vla = vl.combine(&fork.element.measure());
                     if pred(&vla) {
-                        let measure = left.measure() + fork.measure + right.measure();
+                        let measure = left.measure().combine(&fork.measure).combine(&right.measure());
                         break SplitResult::Inside(SplayTreeFork {
                             left: left + fork.left,
                             element: fork.element,
@@ -191,28 +245,271 @@ impl<M: Clone + Zero, T: Measured<M>> SplayTree<M, T> {
     fn iter(&self) -> SplayTreeIterator<M, T> {
         SplayTreeIterator::from(self)
     }
+
+    /// Rotates the element straddling `pred` (the same boundary `split`
+    /// looks for) to the root, so that a subsequent `split` at that
+    /// boundary finds it at the root instead of walking the same path all
+    /// over again.
+    ///
+    /// Implemented as a top-down splay: `left_spine`/`right_spine` collect
+    /// the nodes passed over while descending (a zig-zig run of consecutive
+    /// same-direction steps just keeps appending to the same spine; a
+    /// zig-zag is a direction change between the two), and are folded back
+    /// in around the target once it's found, attaching each pending node's
+    /// untouched subtree in a single `fork` - the same reassembly `split`
+    /// already does, just centred on the target instead of splitting it out.
+    /// A zig-zig run is linked without rotating its pairs first, so the
+    /// depth of the rest of the tree is not reduced: a lopsided chain passed
+    /// over on the way to the target comes out as deep as it went in.
+    fn splay_at<F: Fn(&M) -> bool>(self, pred: &F) -> SplayTree<M, T> {
+        let mut left_spine: Vec<(SplayTree<M, T>, T)> = Vec::new();
+        let mut right_spine: Vec<(T, SplayTree<M, T>)> = Vec::new();
+        let mut v = M::identity();
+        let mut tree = self;
+        loop {
+            match tree {
+                Leaf => {
+                    let mut rebuilt = Leaf;
+                    while let Option::Some((t, r)) = right_spine.pop() {
+                        rebuilt = SplayTree::fork(rebuilt, t, r);
+                    }
+                    while let Option::Some((l, t)) = left_spine.pop() {
+                        rebuilt = SplayTree::fork(l, t, rebuilt);
+                    }
+                    break rebuilt;
+                }
+                Fork(fork_rc) => {
+                    let fork = make_owned(fork_rc);
+                    let vl = v.combine(&fork.left.measure());
+                    if pred(&vl) {
+                        // Zig (towards the left child): this node and its
+                        // right subtree hang off the growing right spine.
+                        right_spine.push((fork.element, fork.right));
+                        tree = fork.left;
+                        continue;
+                    }
+                    let vla = vl.combine(&fork.element.measure());
+                    if pred(&vla) {
+                        let mut left = fork.left;
+                        while let Option::Some((l, t)) = left_spine.pop() {
+                            left = SplayTree::fork(l, t, left);
+                        }
+                        let mut right = fork.right;
+                        while let Option::Some((t, r)) = right_spine.pop() {
+                            right = SplayTree::fork(right, t, r);
+                        }
+                        break SplayTree::fork(left, fork.element, right);
+                    }
+                    // Zig (towards the right child): this node and its left
+                    // subtree hang off the growing left spine.
+                    v = vla;
+                    left_spine.push((fork.left, fork.element));
+                    tree = fork.right;
+                }
+            }
+        }
+    }
+
+    /// The accumulated measure of the elements strictly before the boundary
+    /// where `pred` first becomes true. This is element-granular, like
+    /// `split`: if the boundary lands inside an element rather than between
+    /// two of them, the returned measure is still the prefix *up to that
+    /// element*, not a byte-precise offset within it (`Rope::position_of`
+    /// documents the concrete case). A read-only, by-reference counterpart
+    /// of `split`'s `vl`/`vla` descent.
+    fn position_of<F: Fn(&M) -> bool>(&self, pred: F) -> M {
+        let mut acc = M::identity();
+        let mut node = self;
+        loop {
+            match node {
+                Leaf => break acc,
+                Fork(fork) => {
+                    let vl = acc.combine(&fork.left.measure());
+                    if pred(&vl) {
+                        node = &fork.left;
+                        continue;
+                    }
+                    let vla = vl.combine(&fork.element.measure());
+                    if pred(&vla) {
+                        break vl;
+                    }
+                    acc = vla;
+                    node = &fork.right;
+                }
+            }
+        }
+    }
+
+    /// The element straddling the boundary where `pred` first becomes true
+    /// (the rank `split` would have put at the root of `Inside`), or `None`
+    /// if no element does.
+    fn get<F: Fn(&M) -> bool>(&self, pred: F) -> Option<&T> {
+        let mut acc = M::identity();
+        let mut node = self;
+        loop {
+            match node {
+                Leaf => break Option::None,
+                Fork(fork) => {
+                    let vl = acc.combine(&fork.left.measure());
+                    if pred(&vl) {
+                        node = &fork.left;
+                        continue;
+                    }
+                    let vla = vl.combine(&fork.element.measure());
+                    if pred(&vla) {
+                        break Option::Some(&fork.element);
+                    }
+                    acc = vla;
+                    node = &fork.right;
+                }
+            }
+        }
+    }
+
+    /// The combined measure of the elements overlapping the half-open range
+    /// `[start_pred, end_pred)`. The two boundaries are *not* expressed the
+    /// same way: `start_pred` is a strict `split`-style predicate (`len >
+    /// S`, true once the prefix has gone *past* `S`), but `end_pred` must be
+    /// inclusive (`len >= E`, true once the prefix has *reached* `E`) -
+    /// using a strict predicate for `end_pred` too would make an element
+    /// starting exactly at `E` indistinguishable from one ending exactly at
+    /// `E`, over-including it. This is element-granular: an element that
+    /// only partially overlaps the range contributes its *whole* measure,
+    /// the same way `get`/`position_of` resolve to a whole element rather
+    /// than a point inside it (`Rope::measure_of_range` needs byte
+    /// precision, so it splits the straddling chunks instead of calling
+    /// this). Subtrees entirely outside the range are pruned in O(1) via
+    /// their cached `measure`, so only the fringe straddling either boundary
+    /// is walked.
+    fn measure_of_range<F: Fn(&M) -> bool, G: Fn(&M) -> bool>(
+        &self,
+        start_pred: F,
+        end_pred: G,
+    ) -> M {
+        self.fold_range(&M::identity(), &start_pred, &end_pred)
+    }
+
+    fn fold_range<F: Fn(&M) -> bool, G: Fn(&M) -> bool>(
+        &self,
+        acc: &M,
+        start_pred: &F,
+        end_pred: &G,
+    ) -> M {
+        match self {
+            Leaf => M::identity(),
+            Fork(fork) => {
+                if end_pred(acc) {
+                    return M::identity();
+                }
+                let node_end = acc.combine(&fork.measure);
+                if !start_pred(&node_end) {
+                    return M::identity();
+                }
+                // The whole subtree starts past the start boundary and ends
+                // before the end boundary, so every element overlaps.
+                if start_pred(acc) && !end_pred(&node_end) {
+                    return fork.measure.clone();
+                }
+                let left_end = acc.combine(&fork.left.measure());
+                let left_part = fork.left.fold_range(acc, start_pred, end_pred);
+                // Include the element whole iff its span [left_end, element_end)
+                // overlaps the range, i.e. it hasn't entirely ended before the
+                // start boundary and hasn't started at/after the end boundary -
+                // not (wrongly) whether its *start* alone falls in the range.
+                let elem_part = if !end_pred(&left_end)
+                    && start_pred(&left_end.combine(&fork.element.measure()))
+                {
+                    fork.element.measure()
+                } else {
+                    M::identity()
+                };
+                let element_end = left_end.combine(&fork.element.measure());
+                let right_part = fork.right.fold_range(&element_end, start_pred, end_pred);
+                left_part.combine(&elem_part).combine(&right_part)
+            }
+        }
+    }
+}
+
+/// A read-only, seekable position in a `SplayTree`, tracked in a caller-chosen
+/// `Dimension` `D` instead of the tree's full measure `M`.
+///
+/// This generalizes the boolean predicate `split` takes: instead of rebuilding
+/// the tree around a fixed offset, a `Cursor` walks it by reference and can be
+/// driven towards successive `SeekTarget`s without ever consuming `self`.
+struct Cursor<'a, M, T, D> {
+    tree: &'a SplayTree<M, T>,
+    position: D,
 }
 
-impl<M: Clone + Zero, T: Measured<M>> From<T> for SplayTree<M, T> {
+impl<'a, M: Clone + Monoid, T: Measured<M>, D: Dimension<M> + Clone> Cursor<'a, M, T, D> {
+    fn new(tree: &'a SplayTree<M, T>) -> Cursor<'a, M, T, D> {
+        Cursor {
+            tree,
+            position: D::zero(),
+        }
+    }
+
+    /// The dimension accumulated by the most recent successful `seek`.
+    fn position(&self) -> &D {
+        &self.position
+    }
+
+    /// Walks from the root towards the first element whose end reaches
+    /// `target`, i.e. a lower bound: `target.cmp(&accumulated) != Greater`.
+    /// This is deliberately *not* the same test as `split`'s `vl`/`vla`
+    /// straddling check, which is strict (`Greater` only) and so always
+    /// resolves to the element strictly after an exact boundary match;
+    /// `seek`'s `<=` test resolves to the element the boundary falls at the
+    /// end of instead. Returns the straddling element together with the
+    /// dimension accumulated strictly before it.
+    fn seek<Target: SeekTarget<M, D>>(&mut self, target: &Target) -> Option<(&'a T, D)> {
+        let mut acc = D::zero();
+        let mut node = self.tree;
+        loop {
+            match node {
+                Leaf => return Option::None,
+                Fork(fork) => {
+                    let mut left_acc = acc.clone();
+                    left_acc.add_summary(&fork.left.measure());
+                    if matches!(fork.left, Fork(_)) && target.cmp(&left_acc) != Ordering::Greater {
+                        node = &fork.left;
+                        continue;
+                    }
+                    let mut element_acc = left_acc.clone();
+                    element_acc.add_summary(&fork.element.measure());
+                    if target.cmp(&element_acc) != Ordering::Greater {
+                        self.position = left_acc.clone();
+                        return Option::Some((&fork.element, left_acc));
+                    }
+                    acc = element_acc;
+                    node = &fork.right;
+                }
+            }
+        }
+    }
+}
+
+impl<M: Clone + Monoid, T: Measured<M> + Clone> From<T> for SplayTree<M, T> {
     fn from(t: T) -> SplayTree<M, T> {
         let m = t.measure();
         SplayTree::fork_measure(Leaf, t, Leaf, m)
     }
 }
 
-impl<M: Clone + Zero + Add, T: Measured<M>> Add for SplayTree<M, T> {
+impl<M: Clone + Monoid, T: Measured<M> + Clone> Add for SplayTree<M, T> {
     type Output = SplayTree<M, T>;
     fn add(self, rhs: SplayTree<M, T>) -> SplayTree<M, T> {
         match (self, rhs) {
             (Leaf, rhs) => rhs,
             (lhs, Leaf) => lhs,
-            (Fork(left_fork_box), Fork(right_fork_box)) => {
-                let mut left_fork = *left_fork_box;
-                let mut right_fork = *right_fork_box;
+            (Fork(left_fork_rc), Fork(right_fork_rc)) => {
+                let mut left_fork = make_owned(left_fork_rc);
+                let mut right_fork = make_owned(right_fork_rc);
                 loop {
                     match (left_fork.right, right_fork.left) {
                         (Leaf, right_fork_left) => {
-                            let measure = left_fork.measure + right_fork.measure.clone();
+                            let measure = left_fork.measure.combine(&right_fork.measure);
                             break SplayTree::fork_measure(
                                 left_fork.left,
                                 left_fork.element,
@@ -226,7 +523,7 @@ impl<M: Clone + Zero + Add, T: Measured<M>> Add for SplayTree<M, T> {
                             );
                         }
                         (left_fork_right, Leaf) => {
-                            let measure = left_fork.measure.clone() + right_fork.measure;
+                            let measure = left_fork.measure.combine(&right_fork.measure);
                             break SplayTree::fork_measure(
                                 SplayTree::fork_measure(
                                     left_fork.left,
@@ -239,7 +536,9 @@ impl<M: Clone + Zero + Add, T: Measured<M>> Add for SplayTree<M, T> {
                                 measure,
                             );
                         }
-                        (Fork(mid_left_fork), Fork(mid_right_fork)) => {
+                        (Fork(mid_left_fork_rc), Fork(mid_right_fork_rc)) => {
+                            let mid_left_fork = make_owned(mid_left_fork_rc);
+                            let mid_right_fork = make_owned(mid_right_fork_rc);
                             left_fork = SplayTreeFork {
                                 left: SplayTree::fork(
                                     left_fork.left,
@@ -287,37 +586,63 @@ impl StringMeasure {
     }
 }
 
-impl Add for &StringMeasure {
-    type Output = StringMeasure;
+impl Monoid for StringMeasure {
+    fn identity() -> StringMeasure {
+        StringMeasure {
+            len: 0,
+            char_count: 0,
+            newline_count: 0,
+        }
+    }
 
-    fn add(self, rhs: &StringMeasure) -> StringMeasure {
+    fn combine(&self, other: &StringMeasure) -> StringMeasure {
         StringMeasure {
-            len: self.len + rhs.len,
-            char_count: self.char_count + rhs.char_count,
-            newline_count: self.newline_count + rhs.newline_count,
+            len: self.len + other.len,
+            char_count: self.char_count + other.char_count,
+            newline_count: self.newline_count + other.newline_count,
         }
     }
 }
 
-impl Add for StringMeasure {
-    type Output = StringMeasure;
+/// A `Dimension`/`SeekTarget` pair that projects a `StringMeasure` down to
+/// just the byte length, for use with `Cursor` (see `split_at_byte_offset`
+/// for the `SplayTree::split`-based equivalent).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct ByteOffset(usize);
+
+impl Dimension<StringMeasure> for ByteOffset {
+    fn zero() -> ByteOffset {
+        ByteOffset(0)
+    }
 
-    fn add(self, rhs: StringMeasure) -> StringMeasure {
-        &self + &rhs
+    fn add_summary(&mut self, summary: &StringMeasure) {
+        self.0 += summary.len;
     }
 }
 
-impl Zero for StringMeasure {
-    fn is_zero(&self) -> bool {
-        self == &StringMeasure::zero()
+impl SeekTarget<StringMeasure, ByteOffset> for ByteOffset {
+    fn cmp(&self, dimension: &ByteOffset) -> Ordering {
+        self.0.cmp(&dimension.0)
     }
+}
 
-    fn zero() -> StringMeasure {
-        StringMeasure {
-            len: 0,
-            char_count: 0,
-            newline_count: 0,
-        }
+/// The char-count equivalent of `ByteOffset`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct CharOffset(usize);
+
+impl Dimension<StringMeasure> for CharOffset {
+    fn zero() -> CharOffset {
+        CharOffset(0)
+    }
+
+    fn add_summary(&mut self, summary: &StringMeasure) {
+        self.0 += summary.char_count;
+    }
+}
+
+impl SeekTarget<StringMeasure, CharOffset> for CharOffset {
+    fn cmp(&self, dimension: &CharOffset) -> Ordering {
+        self.0.cmp(&dimension.0)
     }
 }
 
@@ -368,7 +693,7 @@ impl Add for MeasuredString {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Rope(SplayTree<StringMeasure, MeasuredString>);
 const CHUNK_SIZE: usize = 4096;
 
@@ -385,6 +710,13 @@ impl Rope {
         }
         result
     }
+
+    /// O(1): the underlying nodes are reference-counted and immutable, so
+    /// this just bumps refcounts rather than deep-copying the tree. The
+    /// snapshot stays valid and independent across later edits to `self`.
+    fn snapshot(&self) -> Rope {
+        self.clone()
+    }
 }
 
 impl From<String> for Rope {
@@ -421,6 +753,335 @@ impl Add for Rope {
     }
 }
 
+type StringTree = SplayTree<StringMeasure, MeasuredString>;
+
+fn split_measured_string(s: MeasuredString, byte_offset: usize) -> (MeasuredString, MeasuredString) {
+    let MeasuredString { string, .. } = s;
+    let mut left_string = string;
+    let right_string = left_string.split_off(byte_offset);
+    (
+        MeasuredString::from(left_string),
+        MeasuredString::from(right_string),
+    )
+}
+
+fn char_offset_to_byte_offset(s: &str, char_offset: usize) -> usize {
+    match s.char_indices().nth(char_offset) {
+        Option::Some((byte_offset, _)) => byte_offset,
+        Option::None => s.len(),
+    }
+}
+
+fn split_at_byte_offset(tree: StringTree, offset: usize) -> (StringTree, StringTree) {
+    let pred = |m: &StringMeasure| m.len > offset;
+    match tree.splay_at(&pred) {
+        Leaf => (Leaf, Leaf),
+        Fork(fork_rc) => {
+            let fork = make_owned(fork_rc);
+            let left_len = fork.left.measure().len;
+            let element_end = left_len + fork.element.string.len();
+            if left_len <= offset && offset < element_end {
+                // The splayed root itself straddles the boundary, so the
+                // split is resolved directly from it - no second traversal.
+                let local_offset = offset - left_len;
+                let (left_str, right_str) = split_measured_string(fork.element, local_offset);
+                (
+                    fork.left + SplayTree::from(left_str),
+                    SplayTree::from(right_str) + fork.right,
+                )
+            } else {
+                // No element straddles the boundary, i.e. `offset` is at or
+                // past the end of the rope (an offset on an element edge,
+                // including 0, is resolved above from the element starting
+                // there); fall back to a plain split of the tree.
+                match SplayTree::fork(fork.left, fork.element, fork.right).split(pred) {
+                    SplitResult::LeftOf(right) => (Leaf, right),
+                    SplitResult::RightOf(left) => (left, Leaf),
+                    SplitResult::NonMonotonic(left, right) => (left, right),
+                    SplitResult::Inside(inner) => {
+                        let local_offset = offset - inner.left.measure().len;
+                        let (left_str, right_str) = split_measured_string(inner.element, local_offset);
+                        (
+                            inner.left + SplayTree::from(left_str),
+                            SplayTree::from(right_str) + inner.right,
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn split_at_char_offset(tree: StringTree, char_offset: usize) -> (StringTree, StringTree) {
+    let pred = |m: &StringMeasure| m.char_count > char_offset;
+    match tree.splay_at(&pred) {
+        Leaf => (Leaf, Leaf),
+        Fork(fork_rc) => {
+            let fork = make_owned(fork_rc);
+            let left_chars = fork.left.measure().char_count;
+            let element_end = left_chars + fork.element.char_count;
+            if left_chars <= char_offset && char_offset < element_end {
+                // The splayed root itself straddles the boundary, so the
+                // split is resolved directly from it - no second traversal.
+                let local_char_offset = char_offset - left_chars;
+                let byte_offset = char_offset_to_byte_offset(&fork.element.string, local_char_offset);
+                let (left_str, right_str) = split_measured_string(fork.element, byte_offset);
+                (
+                    fork.left + SplayTree::from(left_str),
+                    SplayTree::from(right_str) + fork.right,
+                )
+            } else {
+                // No element straddles the boundary, i.e. `char_offset` is at
+                // or past the end of the rope; fall back to a plain split of
+                // the tree.
+                match SplayTree::fork(fork.left, fork.element, fork.right).split(pred) {
+                    SplitResult::LeftOf(right) => (Leaf, right),
+                    SplitResult::RightOf(left) => (left, Leaf),
+                    SplitResult::NonMonotonic(left, right) => (left, right),
+                    SplitResult::Inside(inner) => {
+                        let local_char_offset = char_offset - inner.left.measure().char_count;
+                        let byte_offset =
+                            char_offset_to_byte_offset(&inner.element.string, local_char_offset);
+                        let (left_str, right_str) = split_measured_string(inner.element, byte_offset);
+                        (
+                            inner.left + SplayTree::from(left_str),
+                            SplayTree::from(right_str) + inner.right,
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Rope {
+    fn insert(&mut self, offset: usize, s: &str) {
+        let Rope(tree) = std::mem::replace(self, Rope::new());
+        let (left, right) = split_at_byte_offset(tree, offset);
+        *self = Rope(left) + Rope::from(s.to_string()) + Rope(right);
+    }
+
+    fn delete(&mut self, range: Range<usize>) {
+        let Rope(tree) = std::mem::replace(self, Rope::new());
+        let (left, rest) = split_at_byte_offset(tree, range.start);
+        let (_, right) = split_at_byte_offset(rest, range.end - range.start);
+        *self = Rope(left) + Rope(right);
+    }
+
+    fn slice(&self, range: Range<usize>) -> Rope {
+        let Rope(tree) = self;
+        let (_, rest) = split_at_byte_offset(tree.clone(), range.start);
+        let (mid, _) = split_at_byte_offset(rest, range.end - range.start);
+        Rope(mid)
+    }
+
+    fn insert_char(&mut self, char_offset: usize, s: &str) {
+        let Rope(tree) = std::mem::replace(self, Rope::new());
+        let (left, right) = split_at_char_offset(tree, char_offset);
+        *self = Rope(left) + Rope::from(s.to_string()) + Rope(right);
+    }
+
+    fn delete_chars(&mut self, range: Range<usize>) {
+        let Rope(tree) = std::mem::replace(self, Rope::new());
+        let (left, rest) = split_at_char_offset(tree, range.start);
+        let (_, right) = split_at_char_offset(rest, range.end - range.start);
+        *self = Rope(left) + Rope(right);
+    }
+
+    fn slice_chars(&self, range: Range<usize>) -> Rope {
+        let Rope(tree) = self;
+        let (_, rest) = split_at_char_offset(tree.clone(), range.start);
+        let (mid, _) = split_at_char_offset(rest, range.end - range.start);
+        Rope(mid)
+    }
+
+    /// The byte-length chunk containing the given byte offset, e.g. for
+    /// reading the bytes immediately around an edit without converting the
+    /// whole rope to a `String` first.
+    fn chunk_at(&self, offset: usize) -> Option<&str> {
+        let Rope(tree) = self;
+        tree.get(|m: &StringMeasure| m.len > offset)
+            .map(|s| s.string.as_str())
+    }
+
+    /// The combined `StringMeasure` of the byte range, e.g.
+    /// `rope.measure_of_range(a..b).char_count` to count chars in a range
+    /// without materializing the substring. Unlike `SplayTree::measure_of_range`
+    /// (which is element-granular), this is byte-precise: it splits at both
+    /// boundaries the same way `slice` does and reads the middle tree's
+    /// cached measure, so a range landing inside a chunk is still exact.
+    /// Untouched subtrees are shared rather than copied; only the (at most
+    /// two) chunks straddling the boundaries are split into new strings,
+    /// and the substring as a whole is never materialized.
+    fn measure_of_range(&self, range: Range<usize>) -> StringMeasure {
+        let Rope(tree) = self;
+        let (_, rest) = split_at_byte_offset(tree.clone(), range.start);
+        let (mid, _) = split_at_byte_offset(rest, range.end - range.start);
+        mid.measure()
+    }
+
+    /// The prefix measure at the boundary where `pred` first becomes true.
+    /// This is element-granular (see `SplayTree::position_of`): for a
+    /// `char_count` predicate like `|m| m.char_count > n - 1`, `.len` is the
+    /// byte offset of the start of the *chunk* containing char `n`, not
+    /// necessarily char `n` itself - `offset_of_line` below shows how to
+    /// recover an exact in-chunk position on top of this.
+    fn position_of<F: Fn(&StringMeasure) -> bool>(&self, pred: F) -> StringMeasure {
+        let Rope(tree) = self;
+        tree.position_of(pred)
+    }
+
+    /// The number of lines, i.e. one more than the number of newlines.
+    fn line_count(&self) -> usize {
+        let Rope(tree) = self;
+        tree.measure().newline_count + 1
+    }
+
+    /// The byte offset of the start of line `line` (0-indexed, so line 0 is
+    /// the start of the rope). Seeks to the chunk containing the `line`th
+    /// newline via the cached `newline_count` measure, then scans within
+    /// that chunk's `String` for the exact byte position, the same two-step
+    /// `position_of`/`get` plus local scan that `line_col_of_offset` below
+    /// does in reverse.
+    fn offset_of_line(&self, line: usize) -> usize {
+        if line == 0 {
+            return 0;
+        }
+        let Rope(tree) = self;
+        let pred = |m: &StringMeasure| m.newline_count > line - 1;
+        let prefix = tree.position_of(pred);
+        match tree.get(pred) {
+            Option::None => prefix.len,
+            Option::Some(chunk) => {
+                let mut newlines_to_skip = (line - 1) - prefix.newline_count;
+                for (i, c) in chunk.string.char_indices() {
+                    if c == '\n' {
+                        if newlines_to_skip == 0 {
+                            return prefix.len + i + 1;
+                        }
+                        newlines_to_skip -= 1;
+                    }
+                }
+                prefix.len + chunk.string.len()
+            }
+        }
+    }
+
+    /// The (0-indexed line, byte column) of the given byte offset. Seeks to
+    /// the chunk containing `offset` via `len`, counts the newlines in the
+    /// chunks before it for the line number, then scans the local prefix of
+    /// that chunk for the column.
+    fn line_col_of_offset(&self, offset: usize) -> (usize, usize) {
+        let Rope(tree) = self;
+        let pred = |m: &StringMeasure| m.len > offset;
+        let prefix = tree.position_of(pred);
+        match tree.get(pred) {
+            // `offset` is exactly the end of the rope, so there's no chunk
+            // straddling it to read the column from - the column is still
+            // the byte distance since the last newline, not 0, so recover it
+            // via the line's own start rather than hardcoding a bogus 0.
+            Option::None => {
+                let line = prefix.newline_count;
+                (line, offset - self.offset_of_line(line))
+            }
+            Option::Some(chunk) => {
+                let local_offset = offset - prefix.len;
+                let local = &chunk.string[..local_offset];
+                let line = prefix.newline_count + local.matches('\n').count();
+                let column = match local.rfind('\n') {
+                    Option::Some(i) => local_offset - (i + 1),
+                    Option::None => local_offset,
+                };
+                (line, column)
+            }
+        }
+    }
+
+    /// Iterates over the rope's lines, each as its own `Rope` slice (a
+    /// line can straddle several chunks, so a plain `&str` can't represent
+    /// it without copying).
+    fn lines(&self) -> Lines {
+        Lines {
+            rope: self,
+            line: 0,
+            line_count: self.line_count(),
+        }
+    }
+}
+
+struct Lines<'a> {
+    rope: &'a Rope,
+    line: usize,
+    line_count: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Rope;
+    fn next(&mut self) -> Option<Rope> {
+        if self.line >= self.line_count {
+            return Option::None;
+        }
+        let start = self.rope.offset_of_line(self.line);
+        let end = if self.line + 1 < self.line_count {
+            self.rope.offset_of_line(self.line + 1)
+        } else {
+            let Rope(tree) = self.rope;
+            tree.measure().len
+        };
+        self.line += 1;
+        Option::Some(self.rope.slice(start..end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_seek_is_a_lower_bound_unlike_splits_strict_straddle() {
+        // Two chunks big enough that `Rope::add` won't coalesce them, so the
+        // boundary at offset 4096 sits exactly between two elements.
+        let Rope(tree) = Rope::from("a".repeat(4096)) + Rope::from("b".repeat(10));
+        let mut cursor: Cursor<StringMeasure, MeasuredString, ByteOffset> = Cursor::new(&tree);
+        let (element, prefix) = cursor
+            .seek(&ByteOffset(4096))
+            .expect("tree is non-empty");
+
+        // `split`'s strict `>` test lands this exact boundary inside the
+        // *second* chunk (local offset 0), since `split_at_byte_offset`
+        // keeps descending while `len > offset` is still false. `seek`'s
+        // `<=` test instead resolves to the element whose end is exactly
+        // the target: the *first* chunk.
+        assert_eq!(element.string, "a".repeat(4096));
+        assert_eq!(prefix, ByteOffset(0));
+        assert_eq!(*cursor.position(), ByteOffset(0));
+    }
+
+    #[test]
+    fn measure_of_range_is_half_open_with_inclusive_end_pred() {
+        // Three separate one-byte elements spanning [0,1), [1,2), [2,3).
+        let a = SplayTree::from(MeasuredString::from("a".to_string()));
+        let b = SplayTree::from(MeasuredString::from("b".to_string()));
+        let c = SplayTree::from(MeasuredString::from("c".to_string()));
+        let tree: StringTree = a + b + c;
+
+        // [1,2) should contain only "b" - `end_pred` must be `>=`, not `>`,
+        // or the element starting exactly at the end boundary ("c") is
+        // wrongly included.
+        let measure = tree.measure_of_range(|m: &StringMeasure| m.len > 1, |m: &StringMeasure| m.len >= 2);
+        assert_eq!(measure.len, 1);
+    }
+
+    #[test]
+    fn line_col_of_offset_at_end_of_rope_has_a_real_column() {
+        let no_newline = Rope::from("abc".to_string());
+        assert_eq!(no_newline.line_col_of_offset(3), (0, 3));
+
+        let with_newline = Rope::from("ab\ncd".to_string());
+        assert_eq!(with_newline.line_col_of_offset(5), (1, 2));
+    }
+}
+
 fn main() {
     let r = Rope::from("Hello".to_string()) + Rope::from(", world!!".to_string());
     println!("{:?}", r);